@@ -1,4 +1,5 @@
 use std::{env, fs};
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{PathBuf, Path};
@@ -68,12 +69,39 @@ pub fn which(cmd: &str) -> Option<PathBuf> {
     None
 }
 
-pub fn tmux_session_list(current: &str) -> Vec<String> {
+// like wrap, but returns None instead of dying on failure
+fn try_wrap(cmd_path: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new(cmd_path).args(args).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+// 0 is tmux's sentinel for "not yet attached"
+fn humanize_ago(ts: u64) -> String {
+    if ts == 0 {
+        return "never".to_string();
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(ts);
+    let secs = now.saturating_sub(ts);
+    match secs {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
+}
+
+// rows for every session but `current`, detached first then most recently attached first
+fn tmux_session_rows(current: &str) -> Vec<(u8, u64, String)> {
     const FMT: &str = "#{?session_attached,0,1} #{?session_last_attached,,0}#{session_last_attached} #{session_name}";
 
     let s = wrap!(tmux, &["list-sessions", "-F", FMT]);
 
-    // Collect (attached_flag, last_attached_ts, name)
     let mut rows: Vec<(u8, u64, String)> = Vec::new();
 
     for line in s.lines() {
@@ -90,30 +118,141 @@ pub fn tmux_session_list(current: &str) -> Vec<String> {
 
     rows.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
 
-    rows.into_iter().map(|(_, _, n)| n).collect()
+    rows
+}
+
+fn session_names(rows: &[(u8, u64, String)]) -> Vec<String> {
+    rows.iter().map(|(_, _, n)| n.clone()).collect()
+}
+
+fn format_session_row(attached: bool, is_previous: bool, last_attached: u64, name: &str, attach_symbol: &str) -> String {
+    let a = if attached { attach_symbol } else { " " };
+    let p = if is_previous { "+" } else { " " };
+    format!("{a}{p} {name} ({})\t{name}", humanize_ago(last_attached))
+}
+
+fn session_display(rows: &[(u8, u64, String)]) -> Vec<String> {
+    let attach_symbol = env::var("RS_TMUX_FZF_ATTACH_SYMBOL").unwrap_or_else(|_| "*".to_string());
+
+    rows.iter().enumerate().map(|(i, (raw_attached, last_attached, name))| {
+        let attached = *raw_attached == 0; // raw flag is inverted, see FMT above
+        let is_previous = i == 0 && !attached && *last_attached > 0;
+        format_session_row(attached, is_previous, *last_attached, name, &attach_symbol)
+    }).collect()
+}
+
+// top of the detached-first sorted list, if it was ever attached; an approximation
+// of tmux's #{client_last_session}, taken across all clients
+fn previous_session(rows: &[(u8, u64, String)]) -> Option<String> {
+    let (raw_attached, last_attached, name) = rows.first()?;
+    (*raw_attached != 0 && *last_attached > 0).then(|| name.clone())
 }
 
 enum Choice {
     FromSelection(String),
-    New(String)
+    New(String),
+    NewAt(String, PathBuf),
+    Previous(String),
 }
 
-fn fzf_pick(prompt: &str, current_session: &str) -> Option<Choice> {
-    let items = tmux_session_list(&current_session);
+// tmux disallows `.` and `:` in session names
+fn sanitize_session_name(name: &str) -> String {
+    name.chars().map(|c| if c == '.' || c == ':' { '_' } else { c }).collect()
+}
+
+// RS_TMUX_FZF_PATHS is colon-separated base dirs; a project root is a child
+// with a .git directory, or one level deeper if the child itself has none
+fn scan_project_dirs() -> Vec<PathBuf> {
+    let paths = match env::var("RS_TMUX_FZF_PATHS") {
+        Ok(p) => p,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut out = Vec::new();
+    for base in paths.split(':').filter(|s| !s.is_empty()) {
+        let Ok(entries) = fs::read_dir(base) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            if path.join(".git").is_dir() {
+                out.push(path);
+                continue;
+            }
+            let Ok(sub_entries) = fs::read_dir(&path) else { continue };
+            for sub in sub_entries.flatten() {
+                let sub_path = sub.path();
+                if sub_path.is_dir() && sub_path.join(".git").is_dir() {
+                    out.push(sub_path);
+                }
+            }
+        }
+    }
+    out
+}
+
+// sanitized basename -> dir, first one found wins on duplicates
+fn project_dir_map(dirs: Vec<PathBuf>) -> HashMap<String, PathBuf> {
+    let mut map = HashMap::new();
+    for dir in dirs {
+        let Some(base) = dir.file_name().and_then(|n| n.to_str()) else { continue };
+        let name = sanitize_session_name(base);
+        if !name.is_empty() {
+            map.entry(name).or_insert(dir);
+        }
+    }
+    map
+}
+
+// sessions first, then project dirs not already matching a live session name
+// (current_session too, since rows never contains it)
+fn open_items(rows: &[(u8, u64, String)], current_session: &str) -> (Vec<String>, HashMap<String, PathBuf>) {
+    let mut items = session_display(rows);
+    let session_set: HashSet<String> = session_names(rows).into_iter()
+        .chain(std::iter::once(current_session.to_string()))
+        .collect();
+
+    let mut dirs = HashMap::new();
+    for (name, dir) in project_dir_map(scan_project_dirs()) {
+        if session_set.contains(&name) {
+            continue;
+        }
+        items.push(format!("   {name}\t{name}"));
+        dirs.insert(name, dir);
+    }
+    (items, dirs)
+}
+
+fn fzf_pick(prompt: &str, current_session: &str, items: Vec<String>, reload_action: &str, previous: Option<&str>) -> Option<Choice> {
+    let mut fzf_args = vec![
+        "--no-multi".to_string(), "--print-query".to_string(),
+        "--bind=alt-enter:print-query".to_string(),
+        format!(
+            concat!(
+                "--bind=ctrl-k:",
+                "execute(tmux kill-session -t {{2}})+",
+                "reload({} {} {})"
+            ),
+            current_exe::path().to_string_lossy(),
+            reload_action,
+            current_session),
+        format!("--preview={} preview-session {{2}}", current_exe::path().to_string_lossy()),
+        "--preview-window=right:50%:wrap".to_string(),
+        "--delimiter=\t".to_string(), "--with-nth=1".to_string(),
+        "--prompt".to_string(), prompt.to_string(),
+    ];
+    // tmux forbids only `.` and `:` in session names, so an arbitrary name
+    // could still break the action-list parsing of `change-query(...)` (via
+    // parens) or get re-expanded by fzf's own placeholder syntax (via
+    // braces); skip the binding for that rare case.
+    if let Some(previous) = previous.filter(|p| !p.contains(['(', ')', '{', '}'])) {
+        // jump straight to the previous session without scrolling/typing
+        fzf_args.push(format!("--bind=ctrl-l:change-query({previous})+accept"));
+    }
 
     let mut child = Command::new(&fzf::path())
-        .args([
-            "--no-multi", "--print-query",
-            "--bind=alt-enter:print-query",
-            format!(
-                concat!(
-                    "--bind=ctrl-k:",
-                    "execute(tmux kill-session -t {{1}})+",
-                    "reload({} ls-switch-from {})"
-                ),
-                current_exe::path().to_string_lossy(),
-                current_session).as_str(),
-            "--prompt", prompt])
+        .args(&fzf_args)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .spawn()
@@ -136,13 +275,26 @@ fn fzf_pick(prompt: &str, current_session: &str) -> Option<Choice> {
     let query = lines.next()
         .unwrap_or_else(|| die(&format!("output is missing first line")));
     let selected = lines.next();
+    // fzf exits 130 when the user aborts (Escape/Ctrl-C); that's always a
+    // cancel, never the accept-with-empty-query case below.
+    let aborted = out.status.code() == Some(130);
     if out.status.success() && selected.is_some() {
-        Some(Choice::FromSelection(selected.unwrap().to_string()))
-    } else {  // no selection was matched
-        if query.is_empty() {
-            tmux_message("canceled");
-            return None;
+        let line = selected.unwrap();
+        let name = line.split_once('\t').map(|(_, name)| name).unwrap_or(line);
+        Some(Choice::FromSelection(name.to_string()))
+    } else if !aborted && query.is_empty() {
+        // accepted with nothing typed/highlighted: default to the previous session, if any
+        match previous {
+            Some(previous) => Some(Choice::Previous(previous.to_string())),
+            None => {
+                tmux_message("canceled");
+                None
+            }
         }
+    } else if aborted {
+        tmux_message("canceled");
+        None
+    } else {
         Some(Choice::New(query.to_string()))
     }
 }
@@ -162,7 +314,7 @@ fn wrap(cmd_path: &Path, args: &[&str], err_display_cb: Option<&mut dyn FnMut(&s
 }
 
 
-const USAGE: &str = "usage: rs-tmux-fzf {switch-from|move-window} <current_session> [<current_window>]";
+const USAGE: &str = "usage: rs-tmux-fzf {switch-from [-r|--read-only] [-d|--detach]|move-window|open} <current_session> [<current_window>]";
 
 fn depends(name: &str) -> PathBuf {
     if let Some(name) = which(name) {
@@ -195,25 +347,106 @@ fn main() {
                 die("You must provide the current session name as the second argument");
                 // wrap!(tmux, ["display-message", "-p", "#{client_session}"])
             });
-            let list = tmux_session_list(&current_session);
+            let list = session_display(&tmux_session_rows(&current_session));
             // output list to stdout, one per line
             for item in list {
                 println!("{}", item);
             }
         }
         "switch-from" => {
-            let current_session = args.next().unwrap_or_else(|| {
+            let mut read_only = false;
+            let mut detach = false;
+            let mut current_session = None;
+            for a in args.by_ref() {
+                match a.as_str() {
+                    "-r" | "--read-only" => read_only = true,
+                    "-d" | "--detach" => detach = true,
+                    _ => { current_session = Some(a); break; }
+                }
+            }
+            let current_session = current_session.unwrap_or_else(|| {
                 die("You must provide the current session name as the second argument");
                 // wrap!(tmux, ["display-message", "-p", "#{client_session}"])
             });
-            if let Some(choice) = fzf_pick("switch to session> ", &current_session) {
+
+            let rows = tmux_session_rows(&current_session);
+            let items = session_display(&rows);
+            let previous = previous_session(&rows);
+            if let Some(choice) = fzf_pick("switch to session> ", &current_session, items, "ls-switch-from", previous.as_deref()) {
                 match choice {
+                    Choice::FromSelection(target) | Choice::Previous(target) => {
+                        // switch-client has no -d flag; kicking other clients off the
+                        // target session is detach-client's job, and it must run before
+                        // we ourselves become attached to that session.
+                        let mut full_args = Vec::new();
+                        if detach { full_args.extend(["detach-client", "-s", &target, ";"]); }
+                        full_args.extend(["switch-client", "-t", &target]);
+                        if read_only { full_args.push("-r"); }
+                        full_args.extend([";", "refresh-client", "-S"]);
+                        wrap!(tmux, &full_args);
+                    }
+                    Choice::New(target) => {
+                        let mut full_args = vec!["new-session", "-d", "-s", &target, ";"];
+                        if detach { full_args.extend(["detach-client", "-s", &target, ";"]); }
+                        full_args.extend(["switch-client", "-t", &target]);
+                        if read_only { full_args.push("-r"); }
+                        full_args.extend([";", "refresh-client", "-S"]);
+                        wrap!(tmux, &full_args);
+                    }
+                    Choice::NewAt(..) => unreachable!("fzf_pick never returns NewAt"),
+                }
+            }
+        }
+        "preview-session" => {
+            let name = args.next().unwrap_or_else(|| {
+                die("You must provide a session name as the second argument");
+            });
+
+            const FMT: &str = "#{window_index}: #{window_name}#{?window_active, (active),} - #{pane_current_command}";
+            match try_wrap(tmux::path(), &["list-windows", "-t", &name, "-F", FMT]) {
+                Some(windows) => {
+                    let last_attached = try_wrap(tmux::path(), &["display-message", "-p", "-t", &name, "#{session_last_attached}"])
+                        .and_then(|s| s.trim().parse::<u64>().ok())
+                        .unwrap_or(0);
+                    println!("last attached: {}", humanize_ago(last_attached));
+                    println!();
+                    print!("{}", windows);
+                }
+                None => println!("new session"),
+            }
+        }
+        "ls-open" => {
+            let current_session = args.next().unwrap_or_else(|| {
+                die("You must provide the current session name as the second argument");
+            });
+            let (items, _) = open_items(&tmux_session_rows(&current_session), &current_session);
+            for item in items {
+                println!("{}", item);
+            }
+        }
+        "open" => {
+            let current_session = args.next().unwrap_or_else(|| {
+                die("You must provide the current session name as the second argument");
+            });
+            let rows = tmux_session_rows(&current_session);
+            let (items, dirs) = open_items(&rows, &current_session);
+            let previous = previous_session(&rows);
+            if let Some(choice) = fzf_pick("open project> ", &current_session, items, "ls-open", previous.as_deref()) {
+                let choice = match choice {
                     Choice::FromSelection(target) => {
+                        match dirs.get(&target) {
+                            Some(dir) => Choice::NewAt(target, dir.clone()),
+                            None => Choice::FromSelection(target),
+                        }
+                    }
+                    other => other,
+                };
+                match choice {
+                    Choice::FromSelection(target) | Choice::Previous(target) => {
                         wrap!(tmux, &[
                             "switch-client", "-t", &target, ";",
                             "refresh-client", "-S"
                         ]);
-
                     }
                     Choice::New(target) => {
                         wrap!(tmux, &[
@@ -222,6 +455,14 @@ fn main() {
                             "refresh-client", "-S"
                         ]);
                     }
+                    Choice::NewAt(target, dir) => {
+                        let dir = dir.to_string_lossy().into_owned();
+                        wrap!(tmux, &[
+                            "new-session", "-d", "-s", &target, "-c", &dir, ";",
+                            "switch-client", "-t", &target, ";",
+                            "refresh-client", "-S"
+                        ]);
+                    }
                 }
             }
         }
@@ -235,9 +476,12 @@ fn main() {
                 // wrap!(tmux, ["display-message", "-p", "#{window_id}"])
             });
 
-            if let Some(choice) = fzf_pick("move window to> ", &current_session) {
+            let rows = tmux_session_rows(&current_session);
+            let items = session_display(&rows);
+            let previous = previous_session(&rows);
+            if let Some(choice) = fzf_pick("move window to> ", &current_session, items, "ls-switch-from", previous.as_deref()) {
                 match choice {
-                    Choice::FromSelection(target) => {
+                    Choice::FromSelection(target) | Choice::Previous(target) => {
                         wrap!(tmux, &[
                             "move-window", "-t", &format!("{target}:"), ";",
                             "switch-client", "-t", &target, ";",
@@ -252,9 +496,121 @@ fn main() {
                             "kill-window", "-t", &format!("{target}:!"),
                         ]);
                     }
+                    Choice::NewAt(..) => unreachable!("fzf_pick never returns NewAt"),
                 }
             }
         }
         _ => die(USAGE),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_session_name_replaces_dots_and_colons() {
+        assert_eq!(sanitize_session_name("foo.bar:baz"), "foo_bar_baz");
+        assert_eq!(sanitize_session_name("plain-name"), "plain-name");
+    }
+
+    #[test]
+    fn humanize_ago_buckets_by_elapsed_time() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+
+        assert_eq!(humanize_ago(0), "never");
+        assert_eq!(humanize_ago(now), "just now");
+        assert_eq!(humanize_ago(now - 120), "2m ago");
+        assert_eq!(humanize_ago(now - 7200), "2h ago");
+        assert_eq!(humanize_ago(now - 172800), "2d ago");
+    }
+
+    #[test]
+    fn format_session_row_encodes_glyphs_and_recoverable_name() {
+        let attached = format_session_row(true, false, 0, "work", "*");
+        assert_eq!(attached, format!("{}{} {} ({})\t{}", "*", " ", "work", "never", "work"));
+
+        let previous = format_session_row(false, true, 0, "other", "*");
+        assert_eq!(previous, format!("{}{} {} ({})\t{}", " ", "+", "other", "never", "other"));
+
+        // whatever the decoration, the bare name must be recoverable after the tab
+        assert_eq!(attached.rsplit('\t').next(), Some("work"));
+    }
+
+    #[test]
+    fn previous_session_is_first_detached_row() {
+        let rows = vec![(1u8, 10u64, "a".to_string()), (0u8, 20u64, "b".to_string())];
+        assert_eq!(previous_session(&rows), Some("a".to_string()));
+
+        let all_attached = vec![(0u8, 10u64, "a".to_string())];
+        assert_eq!(previous_session(&all_attached), None);
+
+        let none: Vec<(u8, u64, String)> = vec![];
+        assert_eq!(previous_session(&none), None);
+    }
+
+    #[test]
+    fn session_names_extracts_bare_names() {
+        let rows = vec![(0u8, 1u64, "x".to_string()), (1u8, 2u64, "y".to_string())];
+        assert_eq!(session_names(&rows), vec!["x".to_string(), "y".to_string()]);
+    }
+
+    // per-test scratch dir, to avoid clashing with parallel runs on the same basenames
+    fn test_scratch_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rs-tmux-fzf-test-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn project_dir_map_sanitizes_and_keeps_first_duplicate() {
+        let tmp = test_scratch_dir("dirmap");
+        fs::create_dir_all(tmp.join("foo.bar")).unwrap();
+        fs::create_dir_all(tmp.join("foo_bar")).unwrap();
+
+        let map = project_dir_map(vec![tmp.join("foo.bar"), tmp.join("foo_bar")]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get("foo_bar"), Some(&tmp.join("foo.bar")));
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn open_items_excludes_current_session_directory() {
+        let tmp = test_scratch_dir("open-items");
+        fs::create_dir_all(tmp.join("myproj/.git")).unwrap();
+        fs::create_dir_all(tmp.join("other/.git")).unwrap();
+
+        env::set_var("RS_TMUX_FZF_PATHS", tmp.to_str().unwrap());
+        let rows: Vec<(u8, u64, String)> = vec![];
+        let (items, dirs) = open_items(&rows, "myproj");
+        env::remove_var("RS_TMUX_FZF_PATHS");
+
+        assert!(!dirs.contains_key("myproj"));
+        assert!(items.iter().all(|line| !line.contains("myproj")));
+        assert!(dirs.contains_key("other"));
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn scan_project_dirs_finds_direct_and_one_level_nested_git_repos() {
+        let tmp = test_scratch_dir("scan");
+        fs::create_dir_all(tmp.join("repo-direct/.git")).unwrap();
+        fs::create_dir_all(tmp.join("org/repo-nested/.git")).unwrap();
+        fs::create_dir_all(tmp.join("org/not-a-repo")).unwrap();
+
+        env::set_var("RS_TMUX_FZF_PATHS", tmp.to_str().unwrap());
+        let mut found = scan_project_dirs();
+        env::remove_var("RS_TMUX_FZF_PATHS");
+        found.sort();
+
+        let mut expected = vec![tmp.join("repo-direct"), tmp.join("org/repo-nested")];
+        expected.sort();
+        assert_eq!(found, expected);
+
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}